@@ -6,26 +6,24 @@ type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 struct Calendar {
     last_id: u64,
     events: Vec<Event>,
+    undo_stack: Vec<UndoRecord>,
+    redo_stack: Vec<UndoRecord>,
 }
 
 impl Calendar {
     fn new() -> Self {
-        Calendar { last_id: 0, events: Vec::new() }
+        Calendar { last_id: 0, events: Vec::new(), undo_stack: Vec::new(), redo_stack: Vec::new() }
     }
     
-    fn load(&mut self) -> Result<()> {
-        let path = ask_details("Enter path to load calendar from: ")?;
-        let file =  match std::fs::read_to_string(path) {
-            Ok(content) => content,
-            Err(e) => {
-                println!("Failed to read file: {}", e);
-                return Ok(());
-            }
-        };
+    /// Parses the pipe-delimited save format (the on-disk contents a
+    /// `NamedCalendar`'s file was read into) and replaces this calendar's
+    /// events with it.
+    fn load_from_str(&mut self, file: &str) {
+        self.events.clear();
         let mut max_id = 0;
         for line in file.lines() {
             let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() == 5 {
+            if (5..=8).contains(&parts.len()) {
                 let id: u64 = match parts[0].parse() {
                     Ok(num) => num,
                     Err(e) => {
@@ -36,36 +34,77 @@ impl Calendar {
                 if id > max_id {
                     max_id = id;
                 }
+                let has_recurrence = parts.len() >= 7;
+                let recurrence = if has_recurrence && !parts[5].is_empty() {
+                    rule_from_string(parts[5]).map(|mut rule| {
+                        if !parts[6].is_empty() {
+                            rule.exceptions = parts[6]
+                                .split(',')
+                                .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                                .collect();
+                        }
+                        rule
+                    })
+                } else {
+                    None
+                };
+                let end_date = match parts.len() {
+                    6 => Some(parts[5]).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+                    8 => Some(parts[7]).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+                    _ => None,
+                };
                 let event = Event {
                     id,
                     title: parts[1].to_string(),
                     date: parts[2].to_string(),
                     time: parts[3].to_string(),
                     description: parts[4].to_string(),
+                    recurrence,
+                    end_date,
                 };
                 self.events.push(event);
             }
         }
         self.last_id = max_id;
-        println!("Calendar loaded successfully.");
-        Ok(())
     }
 
-    fn save(&self) -> Result<()> {
-        let path = ask_details("Enter path to save calendar: ")?;
-        let mut file = std::fs::File::create(path)?;
+    /// Renders this calendar back into the pipe-delimited save format, one
+    /// line per event, ready to be written to its own file. A non-recurring
+    /// event only gets the extra `end_date` field when it has one, so it
+    /// otherwise keeps writing the original five-field line; a recurring
+    /// event always writes all eight fields, leaving `end_date` empty when unset.
+    fn save_lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(self.events.len());
         for event in &self.events {
-            writeln!(file, "{}|{}|{}|{}|{}", event.id, event.title, event.date, event.time, event.description)?;
+            let end_date = event.end_date.clone().unwrap_or_default();
+            match &event.recurrence {
+                Some(rule) => {
+                    let exceptions = rule.exceptions.iter()
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    lines.push(format!("{}|{}|{}|{}|{}|{}|{}|{}", event.id, event.title, event.date, event.time,
+                                        event.description, rule_to_string(rule), exceptions, end_date));
+                }
+                None if !end_date.is_empty() => {
+                    lines.push(format!("{}|{}|{}|{}|{}|{}", event.id, event.title, event.date, event.time,
+                                        event.description, end_date));
+                }
+                None => {
+                    lines.push(format!("{}|{}|{}|{}|{}", event.id, event.title, event.date, event.time, event.description));
+                }
+            }
         }
-        println!("Calendar saved successfully.");
-        Ok(())
+        lines
     }
 
     fn create_event(&mut self) -> Result<()> {
         let title = ask_details("Enter event title: ")?;
-        let date = ask_details("Enter event date (YYYY-MM-DD): ")?;
-        let time = ask_details("Enter event time (HH:MM): ")?;
+        let date = ask_date("Enter event date (YYYY-MM-DD, MM/DD, today, tomorrow, next <weekday>): ", false)?;
+        let end_date = ask_end_date(&date)?;
+        let time = ask_time("Enter event time (HH:MM, 3pm, 3:30 PM): ", false)?;
         let description = ask_details("Enter event description: ")?;
+        let recurrence = ask_recurrence()?;
 
         self.last_id += 1;
 
@@ -75,23 +114,62 @@ impl Calendar {
             date,
             time,
             description,
+            recurrence,
+            end_date,
         };
+        self.undo_stack.push(UndoRecord::Created { event: event.clone() });
+        self.redo_stack.clear();
         self.events.push(event);
         println!("Event created successfully.");
         Ok(())
     }
 
-    fn list_events(&self) -> Result<()> {
+    /// Materializes this calendar's events (expanding recurrences, and
+    /// carrying multi-day events forward under each day they span) that
+    /// fall within `[first, last)`, sorted by datetime - the per-calendar
+    /// "pre-sorted" input the `App`-level k-way merge combines across
+    /// calendars.
+    fn materialize(&self, first: NaiveDate, last: NaiveDate) -> Vec<(NaiveDateTime, &Event)> {
+        let last_date = last - chrono::Duration::days(1);
+        let mut instances = Vec::new();
         for event in &self.events {
-            event.print();
+            let base = match NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            let event_time = NaiveTime::parse_from_str(&event.time, "%H:%M")
+                .unwrap_or_else(|_| NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+            let span = span_days(event, base);
+            let widened_first = first - chrono::Duration::days(span);
+            let starts = match &event.recurrence {
+                Some(rule) => expand_recurrence(base, rule, widened_first, last_date),
+                None => {
+                    if base + chrono::Duration::days(span) >= first && base <= last_date {
+                        vec![base]
+                    } else {
+                        Vec::new()
+                    }
+                }
+            };
+            for start in starts {
+                let span_end = (start + chrono::Duration::days(span)).min(last_date);
+                let mut day = start.max(first);
+                while day <= span_end {
+                    instances.push((day.and_time(event_time), event));
+                    day += chrono::Duration::days(1);
+                }
+            }
         }
-        Ok(())
+        instances.sort_by_key(|(datetime, _)| *datetime);
+        instances
     }
 
     fn delete_event(&mut self) -> Result<()> {
         let id: u64 = ask_u64("Enter the ID of the event to delete: ")?;
         if let Some(pos) = self.events.iter().position(|x| x.id == id) {
-            self.events.remove(pos);
+            let removed = self.events.remove(pos);
+            self.undo_stack.push(UndoRecord::Deleted { event: removed, pos });
+            self.redo_stack.clear();
             println!("Event deleted successfully.");
         } else {
             println!("Event with ID {} not found.", id);
@@ -99,39 +177,20 @@ impl Calendar {
         Ok(())
     }
     
-    fn upcoming_events(&self) -> Result<()> {
-        let now = Local::now();
-        let date = now.format("%Y-%m-%d").to_string();
-        let time = now.format("%H:%M").to_string();
-        let mut upcoming: Vec<&Event> = self.events.iter()
-            .filter(|e| e.date > date || (e.date == date && e.time >= time))
-            .collect();
-        upcoming.sort_by(|a, b| {
-            if a.date == b.date {
-                a.time.cmp(&b.time)
-            } else {
-                a.date.cmp(&b.date)
-            }
-        });
-        // COULD BE DANGEROUS, IN OTHER TIME FORMATS, NEEDS REIMPLEMENTATION
-        for event in upcoming {
-            event.print();
-        }
-        Ok(())
-    }
-
     fn update_event(&mut self) -> Result<()> {
         let id: u64 = ask_u64("Enter the ID of the event to update: ")?;
         if let Some(event) = self.events.iter_mut().find(|e| e.id == id) {
+            self.undo_stack.push(UndoRecord::Updated { before: event.clone() });
+            self.redo_stack.clear();
             let title = ask_details("Enter new event title (leave blank to keep current): ")?;
             if !title.is_empty() {
                 event.title = title;
             }
-            let date = ask_details("Enter new event date (YYYY-MM-DD) (leave blank to keep current): ")?;
+            let date = ask_date("Enter new event date (YYYY-MM-DD, MM/DD, today, tomorrow, next <weekday>) (leave blank to keep current): ", true)?;
             if !date.is_empty() {
                 event.date = date;
             }
-            let time = ask_details("Enter new event time (HH:MM) (leave blank to keep current): ")?;
+            let time = ask_time("Enter new event time (HH:MM, 3pm, 3:30 PM) (leave blank to keep current): ", true)?;
             if !time.is_empty() {
                 event.time = time;
             }
@@ -149,39 +208,1165 @@ impl Calendar {
     fn view(&self) -> Result<()> {
         let id: u64 = ask_u64("Enter the ID of the event to view: ")?;
         if let Some(event) = self.events.iter().find(|e| e.id == id) {
-            event.print();
+            match &event.recurrence {
+                Some(rule) => match NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") {
+                    Ok(base) => {
+                        let today = Local::now().date_naive();
+                        let horizon = today + chrono::Duration::days(365);
+                        let occurrences = expand_recurrence(base, rule, today, horizon);
+                        if occurrences.is_empty() {
+                            event.print();
+                        } else {
+                            for date in occurrences {
+                                event.print_instance(date, &event.time);
+                            }
+                        }
+                    }
+                    Err(_) => event.print(),
+                },
+                None => event.print(),
+            }
         } else {
             println!("Event with ID {} not found.", id);
         }
         Ok(())
     }
 
+    fn undo(&mut self) -> Result<()> {
+        match self.undo_stack.pop() {
+            Some(record) => {
+                let inverse = record.apply(&mut self.events);
+                self.redo_stack.push(inverse);
+                println!("Undid last change.");
+            }
+            None => println!("Nothing to undo."),
+        }
+        Ok(())
+    }
+
+    fn redo(&mut self) -> Result<()> {
+        match self.redo_stack.pop() {
+            Some(record) => {
+                let inverse = record.apply(&mut self.events);
+                self.undo_stack.push(inverse);
+                println!("Redid last change.");
+            }
+            None => println!("Nothing to redo."),
+        }
+        Ok(())
+    }
+
+    /// Resolves `export_md`/`export_html`'s period prompt ('month [YYYY-MM]'
+    /// or 'week [YYYY-MM-DD]') into the Monday-aligned grid bounds to render
+    /// (`[grid_start, grid_end)`) plus a human-readable title.
+    fn ask_grid_range(&self) -> Result<(NaiveDate, NaiveDate, String)> {
+        let input = ask_details("Render which period? ('month' [YYYY-MM], 'week' [YYYY-MM-DD], blank for the current month): ")?;
+        let today = Local::now().date_naive();
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let (first, last, label) = match tokens.as_slice() {
+            [] | ["month"] => {
+                let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
+                let last = add_months(first, 1).unwrap();
+                (first, last, first.format("%B %Y").to_string())
+            }
+            ["month", ym] => {
+                let (y, m) = ym.split_once('-').ok_or("expected month as YYYY-MM")?;
+                let year: i32 = y.parse()?;
+                let month: u32 = m.parse()?;
+                let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or("invalid month")?;
+                let last = add_months(first, 1).ok_or("invalid month")?;
+                (first, last, first.format("%B %Y").to_string())
+            }
+            ["week"] => {
+                let offset = today.weekday().num_days_from_monday() as i64;
+                let first = today - chrono::Duration::days(offset);
+                (first, first + chrono::Duration::days(7), format!("Week of {}", first.format("%Y-%m-%d")))
+            }
+            ["week", date_str] => {
+                let date = parse_flexible_date(date_str).ok_or("could not understand that date")?;
+                let offset = date.weekday().num_days_from_monday() as i64;
+                let first = date - chrono::Duration::days(offset);
+                (first, first + chrono::Duration::days(7), format!("Week of {}", first.format("%Y-%m-%d")))
+            }
+            _ => return Err("expected 'month', 'month YYYY-MM', 'week', or 'week YYYY-MM-DD'".into()),
+        };
+
+        let (grid_start, grid_end) = pad_to_week_grid(first, last);
+        Ok((grid_start, grid_end, label))
+    }
+
+    /// Materializes every day in `[grid_start, grid_end)` (including
+    /// recurring instances, each multi-day event carried forward under
+    /// every day it spans) into a `title`/`time` line list per day, for
+    /// rendering by `export_md`/`export_html`.
+    fn day_cells(&self, grid_start: NaiveDate, grid_end: NaiveDate) -> Vec<(NaiveDate, Vec<String>)> {
+        let mut day_events: std::collections::BTreeMap<NaiveDate, Vec<(String, String)>> = std::collections::BTreeMap::new();
+        let grid_last = grid_end - chrono::Duration::days(1);
+        for event in &self.events {
+            let base = match NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            let span = span_days(event, base);
+            let widened_start = grid_start - chrono::Duration::days(span);
+            let starts = match &event.recurrence {
+                Some(rule) => expand_recurrence(base, rule, widened_start, grid_last),
+                None => {
+                    if base + chrono::Duration::days(span) >= grid_start && base < grid_end {
+                        vec![base]
+                    } else {
+                        Vec::new()
+                    }
+                }
+            };
+            let title = event.title.replace(['\n', '\r'], " ");
+            for start in starts {
+                let span_end = (start + chrono::Duration::days(span)).min(grid_last);
+                let mut day = start.max(grid_start);
+                while day <= span_end {
+                    day_events.entry(day).or_default().push((event.time.clone(), title.clone()));
+                    day += chrono::Duration::days(1);
+                }
+            }
+        }
+        for entries in day_events.values_mut() {
+            entries.sort();
+        }
+
+        let mut cells = Vec::new();
+        let mut day = grid_start;
+        while day < grid_end {
+            let lines = day_events.get(&day)
+                .map(|entries| entries.iter().map(|(time, title)| format!("{} {}", time, title)).collect())
+                .unwrap_or_default();
+            cells.push((day, lines));
+            day += chrono::Duration::days(1);
+        }
+        cells
+    }
+
+    fn export_md(&self) -> Result<()> {
+        let (grid_start, grid_end, label) = self.ask_grid_range()?;
+        let path = ask_details("Enter path to save the Markdown calendar to: ")?;
+        let cells = self.day_cells(grid_start, grid_end);
+        let mut file = std::fs::File::create(path)?;
+        writeln!(file, "# {}\n", label)?;
+        writeln!(file, "| Mon | Tue | Wed | Thu | Fri | Sat | Sun |")?;
+        writeln!(file, "|---|---|---|---|---|---|---|")?;
+        for week in cells.chunks(7) {
+            let row: Vec<String> = week.iter().map(|(date, lines)| {
+                let mut cell = format!("**{}**", date.day());
+                for line in lines {
+                    cell.push_str(&format!("<br>- {}", escape_md_table_cell(line)));
+                }
+                cell
+            }).collect();
+            writeln!(file, "| {} |", row.join(" | "))?;
+        }
+        println!("Calendar exported to Markdown successfully.");
+        Ok(())
+    }
+
+    fn export_html(&self) -> Result<()> {
+        let (grid_start, grid_end, label) = self.ask_grid_range()?;
+        let path = ask_details("Enter path to save the HTML calendar to: ")?;
+        let cells = self.day_cells(grid_start, grid_end);
+        let mut file = std::fs::File::create(path)?;
+        writeln!(file, "<!DOCTYPE html>")?;
+        writeln!(file, "<html><head><meta charset=\"utf-8\"><title>{}</title>", html_escape(&label))?;
+        writeln!(file, "<style>table {{ border-collapse: collapse; width: 100%; }} th, td {{ border: 1px solid #ccc; padding: 4px; vertical-align: top; width: 14%; }} ul {{ margin: 0; padding-left: 1.2em; }}</style>")?;
+        writeln!(file, "</head><body>")?;
+        writeln!(file, "<h1>{}</h1>", html_escape(&label))?;
+        writeln!(file, "<table>")?;
+        writeln!(file, "<tr><th>Mon</th><th>Tue</th><th>Wed</th><th>Thu</th><th>Fri</th><th>Sat</th><th>Sun</th></tr>")?;
+        for week in cells.chunks(7) {
+            writeln!(file, "<tr>")?;
+            for (date, lines) in week {
+                write!(file, "<td><strong>{}</strong><ul>", date.day())?;
+                for line in lines {
+                    write!(file, "<li>{}</li>", html_escape(line))?;
+                }
+                write!(file, "</ul></td>")?;
+            }
+            writeln!(file, "</tr>")?;
+        }
+        writeln!(file, "</table>")?;
+        writeln!(file, "</body></html>")?;
+        println!("Calendar exported to HTML successfully.");
+        Ok(())
+    }
+
+    fn import_ics(&mut self) -> Result<()> {
+        let path = ask_details("Enter path to .ics file to import: ")?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                println!("Failed to read file: {}", e);
+                return Ok(());
+            }
+        };
+        let lines = ics_unfold(&content);
+        let mut imported = 0;
+        let mut i = 0;
+        while i < lines.len() {
+            if lines[i] == "BEGIN:VEVENT" {
+                i += 1;
+                let mut title = String::new();
+                let mut description = String::new();
+                let mut date = String::new();
+                let mut time = "00:00".to_string();
+                let mut end_date: Option<String> = None;
+                let mut uid: Option<String> = None;
+                let mut recurrence: Option<RecurrenceRule> = None;
+                let mut exceptions: Vec<NaiveDate> = Vec::new();
+                while i < lines.len() && lines[i] != "END:VEVENT" {
+                    let (name_and_params, value) = split_ics_property(&lines[i]);
+                    let name = name_and_params.split(';').next().unwrap_or("");
+                    match name {
+                        "SUMMARY" => title = ics_unescape_text(value),
+                        "DESCRIPTION" => description = ics_unescape_text(value),
+                        "UID" => uid = Some(value.to_string()),
+                        "DTSTART" => {
+                            if let Some((d, t)) = parse_ics_datetime_value(value) {
+                                date = d;
+                                time = t;
+                            }
+                        }
+                        "DTEND" => {
+                            if let Some((d, _)) = parse_ics_datetime_value(value) {
+                                end_date = Some(d);
+                            }
+                        }
+                        "RRULE" => recurrence = rule_from_string(value),
+                        "EXDATE" => {
+                            for part in value.split(',') {
+                                let parsed = parse_ics_datetime_value(part)
+                                    .and_then(|(d, _)| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+                                if let Some(d) = parsed {
+                                    exceptions.push(d);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                if date.is_empty() {
+                    match end_date.take() {
+                        Some(d) => date = d,
+                        None => {
+                            i += 1;
+                            continue;
+                        }
+                    }
+                }
+                if let Some(rule) = recurrence.as_mut() {
+                    rule.exceptions = exceptions;
+                }
+                let id = uid.as_deref()
+                    .and_then(extract_id_from_uid)
+                    .unwrap_or_else(|| {
+                        self.last_id += 1;
+                        self.last_id
+                    });
+                if id > self.last_id {
+                    self.last_id = id;
+                }
+                self.events.push(Event { id, title, date, time, description, recurrence, end_date });
+                imported += 1;
+            }
+            i += 1;
+        }
+        println!("Imported {} event(s).", imported);
+        Ok(())
+    }
+
+    fn export_ics(&self) -> Result<()> {
+        let path = ask_details("Enter path to export calendar to (.ics): ")?;
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(b"BEGIN:VCALENDAR\r\n")?;
+        file.write_all(b"VERSION:2.0\r\n")?;
+        file.write_all(b"PRODID:-//rust-cli-calendar//EN\r\n")?;
+        for event in &self.events {
+            file.write_all(b"BEGIN:VEVENT\r\n")?;
+            file.write_all(ics_fold_line(&format!("UID:event-{}@rust-cli-calendar", event.id)).as_bytes())?;
+            file.write_all(ics_fold_line(&format!("SUMMARY:{}", ics_escape_text(&event.title))).as_bytes())?;
+            file.write_all(ics_fold_line(&format!("DTSTART:{}", format_ics_datetime(&event.date, &event.time))).as_bytes())?;
+            if let Some(end_date) = &event.end_date {
+                file.write_all(ics_fold_line(&format!("DTEND:{}", format_ics_datetime(end_date, &event.time))).as_bytes())?;
+            }
+            file.write_all(ics_fold_line(&format!("DESCRIPTION:{}", ics_escape_text(&event.description))).as_bytes())?;
+            if let Some(rule) = &event.recurrence {
+                file.write_all(ics_fold_line(&format!("RRULE:{}", rule_to_string(rule))).as_bytes())?;
+                if !rule.exceptions.is_empty() {
+                    let exdates = rule.exceptions.iter()
+                        .map(|d| format_ics_datetime(&d.format("%Y-%m-%d").to_string(), &event.time))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    file.write_all(ics_fold_line(&format!("EXDATE:{}", exdates)).as_bytes())?;
+                }
+            }
+            file.write_all(b"END:VEVENT\r\n")?;
+        }
+        file.write_all(b"END:VCALENDAR\r\n")?;
+        println!("Calendar exported successfully.");
+        Ok(())
+    }
+}
+
+/// One loaded calendar plus the name the user refers to it by and the file
+/// it was loaded from (if any), so `save` can write it back without
+/// re-prompting.
+struct NamedCalendar {
+    name: String,
+    path: Option<String>,
+    calendar: Calendar,
+}
+
+/// Manages every loaded `Calendar` and which one the single-calendar commands
+/// (`create`/`delete`/`update`/`view`/`undo`/`redo`/the ICS and grid
+/// exports) target. Read-only commands that make sense across a whole
+/// agenda (`list`/`agenda`/`upcoming`/`search`) instead merge all loaded
+/// calendars together.
+struct App {
+    calendars: Vec<NamedCalendar>,
+    active: usize,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            calendars: vec![NamedCalendar { name: "default".to_string(), path: None, calendar: Calendar::new() }],
+            active: 0,
+        }
+    }
+
+    fn active(&self) -> &Calendar {
+        &self.calendars[self.active].calendar
+    }
+
+    fn active_mut(&mut self) -> &mut Calendar {
+        &mut self.calendars[self.active].calendar
+    }
+
+    fn use_calendar(&mut self, name: &str) -> Result<()> {
+        match self.calendars.iter().position(|c| c.name == name) {
+            Some(idx) => {
+                self.active = idx;
+                println!("Switched to calendar '{}'.", name);
+            }
+            None => println!("No calendar named '{}'. Use 'load' to add one.", name),
+        }
+        Ok(())
+    }
+
+    fn list_calendars(&self) -> Result<()> {
+        for (i, named) in self.calendars.iter().enumerate() {
+            let marker = if i == self.active { "*" } else { " " };
+            let path = named.path.as_deref().unwrap_or("(not yet saved)");
+            println!("{} {} - {} event(s) - {}", marker, named.name, named.calendar.events.len(), path);
+        }
+        Ok(())
+    }
+
+    /// Loads a named calendar from a file, creating it if the name hasn't
+    /// been used yet, and makes it the active calendar.
+    fn load(&mut self) -> Result<()> {
+        let name = ask_details("Enter a name for this calendar: ")?;
+        let path = ask_details("Enter path to load calendar from: ")?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                println!("Failed to read file: {}", e);
+                return Ok(());
+            }
+        };
+        let idx = match self.calendars.iter().position(|c| c.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.calendars.push(NamedCalendar { name: name.clone(), path: None, calendar: Calendar::new() });
+                self.calendars.len() - 1
+            }
+        };
+        self.calendars[idx].calendar.load_from_str(&content);
+        self.calendars[idx].path = Some(path);
+        self.active = idx;
+        println!("Calendar '{}' loaded successfully.", name);
+        Ok(())
+    }
+
+    /// Saves every loaded calendar back to its own file, prompting for a
+    /// path for any calendar that hasn't been saved or loaded from one yet.
+    fn save(&mut self) -> Result<()> {
+        for named in &mut self.calendars {
+            let path = match &named.path {
+                Some(path) => path.clone(),
+                None => ask_details(&format!("Enter path to save calendar '{}' to: ", named.name))?,
+            };
+            let mut file = std::fs::File::create(&path)?;
+            for line in named.calendar.save_lines() {
+                writeln!(file, "{}", line)?;
+            }
+            named.path = Some(path);
+        }
+        println!("All calendars saved successfully.");
+        Ok(())
+    }
+
+    /// Materializes every loaded calendar over `[first, last)` and k-way
+    /// merges the (already datetime-sorted) per-calendar results into one
+    /// chronological sequence, each instance tagged with its source
+    /// calendar's name.
+    fn gather(&self, first: NaiveDate, last: NaiveDate) -> Vec<(NaiveDateTime, &str, &Event)> {
+        let sources: Vec<(&str, Vec<(NaiveDateTime, &Event)>)> = self.calendars.iter()
+            .map(|named| (named.name.as_str(), named.calendar.materialize(first, last)))
+            .collect();
+        merge_tagged(sources)
+    }
+
+    /// Lists every event across all calendars: non-recurring events are
+    /// always shown regardless of date, recurring ones are expanded up to
+    /// a year out. Merged into one chronological, source-tagged sequence.
+    fn list(&self) -> Result<()> {
+        let today = Local::now().date_naive();
+        let horizon = today + chrono::Duration::days(365);
+        let mut instances: Vec<(NaiveDateTime, &str, &Event)> = Vec::new();
+        for named in &self.calendars {
+            for event in &named.calendar.events {
+                let event_time = NaiveTime::parse_from_str(&event.time, "%H:%M")
+                    .unwrap_or_else(|_| NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+                match &event.recurrence {
+                    Some(rule) => {
+                        if let Ok(base) = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") {
+                            for date in expand_recurrence(base, rule, today, horizon) {
+                                instances.push((date.and_time(event_time), named.name.as_str(), event));
+                            }
+                        }
+                    }
+                    None => {
+                        let date = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d").unwrap_or(today);
+                        instances.push((date.and_time(event_time), named.name.as_str(), event));
+                    }
+                }
+            }
+        }
+        instances.sort_by_key(|(datetime, _, _)| *datetime);
+        for (datetime, name, event) in instances {
+            print_tagged_instance(name, event, datetime);
+        }
+        Ok(())
+    }
+
+    fn upcoming(&self) -> Result<()> {
+        let now = Local::now().naive_local();
+        let today = now.date();
+        let horizon = today + chrono::Duration::days(365);
+        for (datetime, name, event) in self.gather(today, horizon) {
+            if datetime >= now {
+                print_tagged_instance(name, event, datetime);
+            }
+        }
+        Ok(())
+    }
+
+    fn agenda(&self, args: &str) -> Result<()> {
+        let input = if args.is_empty() {
+            ask_details("Enter period (day/week/month) or a date range (YYYY-MM-DD YYYY-MM-DD): ")?
+        } else {
+            args.to_string()
+        };
+        let today = Local::now().date_naive();
+        let (first, last) = match parse_agenda_range(&input, today) {
+            Some(range) => range,
+            None => {
+                println!("Could not understand '{}'. Use day, week, month, or 'YYYY-MM-DD YYYY-MM-DD'.", input);
+                return Ok(());
+            }
+        };
+
+        let instances = self.gather(first, last);
+        if instances.is_empty() {
+            println!("No events in that range.");
+            return Ok(());
+        }
+        let mut current_day: Option<NaiveDate> = None;
+        for (datetime, name, event) in instances {
+            let day = datetime.date();
+            if current_day != Some(day) {
+                println!("-- {} --", day.format("%Y-%m-%d (%A)"));
+                current_day = Some(day);
+            }
+            println!("  {}  [{}] {} - {}", datetime.format("%H:%M"), name, event.title, event.description);
+        }
+        Ok(())
+    }
+
+    /// Searches every event in every calendar by title/description
+    /// substring, with no date restriction, merged into one chronological,
+    /// source-tagged sequence.
     fn search(&self) -> Result<()> {
         let query = ask_details("Enter search query: ")?;
-        for event in &self.events {
-            if event.title.contains(&query) || event.description.contains(&query) {
-                event.print();
+        let today = Local::now().date_naive();
+        let mut matches: Vec<(NaiveDateTime, &str, &Event)> = Vec::new();
+        for named in &self.calendars {
+            for event in &named.calendar.events {
+                if !event.title.contains(&query) && !event.description.contains(&query) {
+                    continue;
+                }
+                let date = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d").unwrap_or(today);
+                let time = NaiveTime::parse_from_str(&event.time, "%H:%M")
+                    .unwrap_or_else(|_| NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+                matches.push((date.and_time(time), named.name.as_str(), event));
             }
         }
+        matches.sort_by_key(|(datetime, _, _)| *datetime);
+        for (datetime, name, event) in matches {
+            print_tagged_instance(name, event, datetime);
+        }
         Ok(())
     }
 }
 
+fn print_tagged_instance(calendar_name: &str, event: &Event, datetime: NaiveDateTime) {
+    println!("==========================");
+    println!("Calendar: {}\nID: {}\nTitle: {}\nDatetime: {}\nDescription: {}",
+             calendar_name, event.id, event.title, datetime.format("%Y-%m-%d %H:%M"), event.description);
+    println!("==========================\n");
+}
+
+/// K-way merges each calendar's pre-sorted `(datetime, event)` list into one
+/// chronological sequence, tagging every instance with its source
+/// calendar's name.
+fn merge_tagged<'a>(sources: Vec<(&'a str, Vec<(NaiveDateTime, &'a Event)>)>) -> Vec<(NaiveDateTime, &'a str, &'a Event)> {
+    let names: Vec<&str> = sources.iter().map(|(name, _)| *name).collect();
+    let mut iters: Vec<std::slice::Iter<(NaiveDateTime, &Event)>> = sources.iter().map(|(_, v)| v.iter()).collect();
+    let mut heads: Vec<Option<(NaiveDateTime, &Event)>> = iters.iter_mut().map(|it| it.next().copied()).collect();
+
+    let mut merged = Vec::new();
+    loop {
+        let min_idx = heads.iter().enumerate()
+            .filter_map(|(i, head)| head.map(|(dt, _)| (i, dt)))
+            .min_by_key(|(_, dt)| *dt)
+            .map(|(i, _)| i);
+        match min_idx {
+            Some(i) => {
+                let (datetime, event) = heads[i].unwrap();
+                merged.push((datetime, names[i], event));
+                heads[i] = iters[i].next().copied();
+            }
+            None => break,
+        }
+    }
+    merged
+}
+
+/// Unfolds RFC 5545 line continuations (a CRLF followed by a single leading
+/// space or tab is a soft break) into logical property lines.
+fn ics_unfold(content: &str) -> Vec<String> {
+    let mut logical_lines: Vec<String> = Vec::new();
+    for raw_line in content.split('\n') {
+        let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        if (line.starts_with(' ') || line.starts_with('\t')) && !logical_lines.is_empty() {
+            logical_lines.last_mut().unwrap().push_str(&line[1..]);
+        } else if !line.is_empty() {
+            logical_lines.push(line.to_string());
+        }
+    }
+    logical_lines
+}
+
+/// Folds a logical property line to 75 octets per continuation segment,
+/// each continuation prefixed with a single space, terminated with CRLF.
+fn ics_fold_line(line: &str) -> String {
+    const MAX: usize = 75;
+    let mut out = String::new();
+    let mut current = String::new();
+    for ch in line.chars() {
+        if current.len() + ch.len_utf8() > MAX {
+            out.push_str(&current);
+            out.push_str("\r\n");
+            current = String::new();
+            current.push(' ');
+        }
+        current.push(ch);
+    }
+    out.push_str(&current);
+    out.push_str("\r\n");
+    out
+}
+
+/// Splits a property line into its "NAME;PARAM=VAL" head and raw value,
+/// on the first colon; a quoted parameter value containing a colon is not
+/// handled.
+fn split_ics_property(line: &str) -> (&str, &str) {
+    match line.split_once(':') {
+        Some((name, value)) => (name, value),
+        None => (line, ""),
+    }
+}
+
+fn ics_escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn ics_unescape_text(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(',') => out.push(','),
+                Some(';') => out.push(';'),
+                Some('\\') => out.push('\\'),
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses a DTSTART/DTEND value (date-only `VALUE=DATE` form or a
+/// `DateTime`) into our `date`/`time` strings. A trailing `Z` is dropped
+/// here; any `TZID` param has already been split off by the caller. The
+/// time is kept as written, with no time zone conversion.
+fn parse_ics_datetime_value(value: &str) -> Option<(String, String)> {
+    let value = value.trim_end_matches('Z');
+    if let Some((date_part, time_part)) = value.split_once('T') {
+        if date_part.len() != 8 || time_part.len() < 4 {
+            return None;
+        }
+        let date = format!("{}-{}-{}", &date_part[0..4], &date_part[4..6], &date_part[6..8]);
+        let time = format!("{}:{}", &time_part[0..2], &time_part[2..4]);
+        Some((date, time))
+    } else if value.len() == 8 {
+        let date = format!("{}-{}-{}", &value[0..4], &value[4..6], &value[6..8]);
+        Some((date, "00:00".to_string()))
+    } else {
+        None
+    }
+}
+
+/// Formats our `date`/`time` strings back into an RFC 5545 local `DateTime`
+/// value (`YYYYMMDDTHHMMSS`).
+fn format_ics_datetime(date: &str, time: &str) -> String {
+    let date_part: String = date.chars().filter(|c| *c != '-').collect();
+    let mut time_digits: String = time.chars().filter(|c| *c != ':').collect();
+    while time_digits.len() < 6 {
+        time_digits.push('0');
+    }
+    format!("{}T{}", date_part, time_digits)
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Escapes `|` so a day's event line can't add a bogus column to
+/// `export_md`'s `|`-delimited grid table.
+fn escape_md_table_cell(line: &str) -> String {
+    line.replace('|', "\\|")
+}
+
+/// Recovers the numeric event id from a UID we generated ourselves on a
+/// previous export, so round-tripped events keep their original id.
+fn extract_id_from_uid(uid: &str) -> Option<u64> {
+    uid.strip_prefix("event-")
+        .and_then(|rest| rest.split('@').next())
+        .and_then(|id| id.parse().ok())
+}
+
+/// A reversible record of one mutating command, pushed onto `Calendar`'s
+/// undo stack by that command. Undoing and redoing both
+/// call `apply`, which performs the inverse of the stored action and
+/// returns a record that undoes *that* - so the same record flows back
+/// and forth between the undo and redo stacks.
+enum UndoRecord {
+    Created { event: Event },
+    Deleted { event: Event, pos: usize },
+    Updated { before: Event },
+}
+
+impl UndoRecord {
+    fn apply(self, events: &mut Vec<Event>) -> UndoRecord {
+        match self {
+            UndoRecord::Created { event } => {
+                let id = event.id;
+                match events.iter().position(|e| e.id == id) {
+                    Some(pos) => UndoRecord::Deleted { event: events.remove(pos), pos },
+                    None => UndoRecord::Created { event },
+                }
+            }
+            UndoRecord::Deleted { event, pos } => {
+                let pos = pos.min(events.len());
+                events.insert(pos, event.clone());
+                UndoRecord::Created { event }
+            }
+            UndoRecord::Updated { before } => {
+                let id = before.id;
+                match events.iter_mut().find(|e| e.id == id) {
+                    Some(slot) => UndoRecord::Updated { before: std::mem::replace(slot, before) },
+                    None => UndoRecord::Updated { before },
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 struct Event {
     id: u64,
     title: String,
     date: String,
     time: String,
     description: String,
+    recurrence: Option<RecurrenceRule>,
+    /// Inclusive last day a multi-day event spans. `None` (or a date not
+    /// after `date`) means the event is a single-day event.
+    end_date: Option<String>,
 }
 
 impl Event {
     fn print(&self) {
         println!("==========================");
-        println!("ID: {}\nTitle: {}\nDatetime: {} {}\nDescription: {}", 
-                 self.id, self.title, self.date, self.time, self.description);
+        println!("ID: {}\nTitle: {}\nDatetime: {} {}{}\nDescription: {}",
+                 self.id, self.title, self.date, self.time, self.span_suffix(), self.description);
         println!("==========================\n");
     }
+
+    /// Prints a single materialized occurrence of a (possibly recurring)
+    /// event, tagged with its concrete `date` rather than the template's.
+    fn print_instance(&self, date: NaiveDate, time: &str) {
+        println!("==========================");
+        println!("ID: {}\nTitle: {}\nDatetime: {} {}{}\nDescription: {}",
+                 self.id, self.title, date.format("%Y-%m-%d"), time, self.span_suffix(), self.description);
+        println!("==========================\n");
+    }
+
+    /// " (through YYYY-MM-DD)" when this event spans more than one day, else empty.
+    fn span_suffix(&self) -> String {
+        match &self.end_date {
+            Some(end) if end.as_str() > self.date.as_str() => format!(" (through {})", end),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Clone, Debug)]
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: u32,
+    /// 7-bit mask, bit 0 = Monday .. bit 6 = Sunday. Only meaningful for `Weekly`.
+    weekdays: Option<u8>,
+    until: Option<NaiveDate>,
+    count: Option<u32>,
+    exceptions: Vec<NaiveDate>,
+}
+
+/// Number of extra days `event` spans past its (occurrence) start date, so
+/// `materialize`/`day_cells` can carry it forward under each day it covers.
+/// Zero for a single-day event or one whose `end_date` doesn't parse.
+fn span_days(event: &Event, base: NaiveDate) -> i64 {
+    event.end_date.as_deref()
+        .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+        .map(|end| (end - base).num_days().max(0))
+        .unwrap_or(0)
+}
+
+/// Expands `rule` starting from the event's `base` date into concrete
+/// occurrence dates that fall within `[first, last]`.
+fn expand_recurrence(base: NaiveDate, rule: &RecurrenceRule, first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+    let exceptions: std::collections::HashSet<NaiveDate> = rule.exceptions.iter().cloned().collect();
+    let mut occurrences = Vec::new();
+    let mut generated: u32 = 0;
+    let mut step: i64 = 0;
+    let interval = rule.interval.max(1) as i64;
+
+    loop {
+        if let Some(count) = rule.count
+            && generated >= count
+        {
+            break;
+        }
+
+        let step_date = match rule.frequency {
+            Frequency::Daily => base + chrono::Duration::days(step * interval),
+            Frequency::Weekly => base + chrono::Duration::days(step * 7 * interval),
+            Frequency::Monthly => match add_months(base, step * interval) {
+                Some(d) => d,
+                None => break,
+            },
+        };
+
+        let candidates: Vec<NaiveDate> = if rule.frequency == Frequency::Weekly {
+            match rule.weekdays {
+                Some(mask) => {
+                    let days_from_monday = base.weekday().num_days_from_monday() as i64;
+                    let week_start = (base - chrono::Duration::days(days_from_monday))
+                        + chrono::Duration::days(step * 7 * interval);
+                    (0..7)
+                        .filter(|offset| mask & (1 << offset) != 0)
+                        .map(|offset| week_start + chrono::Duration::days(offset))
+                        .filter(|d| *d >= base)
+                        .collect()
+                }
+                None => vec![step_date],
+            }
+        } else {
+            vec![step_date]
+        };
+
+        for date in &candidates {
+            if let Some(until) = rule.until
+                && *date > until
+            {
+                continue;
+            }
+            if let Some(count) = rule.count
+                && generated >= count
+            {
+                break;
+            }
+            generated += 1;
+            if *date >= first && *date <= last && !exceptions.contains(date) {
+                occurrences.push(*date);
+            }
+        }
+
+        let past_until = rule.until.map(|until| step_date > until).unwrap_or(false);
+        if step_date > last || past_until {
+            break;
+        }
+        step += 1;
+    }
+
+    occurrences.sort();
+    occurrences
+}
+
+/// Adds (possibly negative) whole months to `date`, clamping the day of
+/// month down when it overflows the target month's length.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let total = date.year() as i64 * 12 + (date.month() as i64 - 1) + months;
+    let year = total.div_euclid(12) as i32;
+    let month = (total.rem_euclid(12) + 1) as u32;
+    let mut day = date.day();
+    loop {
+        if let Some(result) = NaiveDate::from_ymd_opt(year, month, day) {
+            return Some(result);
+        }
+        if day == 0 {
+            return None;
+        }
+        day -= 1;
+    }
+}
+
+/// Pads `[first, last)` out to whole Monday-aligned weeks, so a month or
+/// week grid always renders complete rows.
+fn pad_to_week_grid(first: NaiveDate, last: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start_offset = first.weekday().num_days_from_monday() as i64;
+    let grid_start = first - chrono::Duration::days(start_offset);
+    let last_day = last - chrono::Duration::days(1);
+    let end_offset = 6 - last_day.weekday().num_days_from_monday() as i64;
+    let grid_end = last_day + chrono::Duration::days(end_offset + 1);
+    (grid_start, grid_end)
+}
+
+fn rule_to_string(rule: &RecurrenceRule) -> String {
+    let freq = match rule.frequency {
+        Frequency::Daily => "DAILY",
+        Frequency::Weekly => "WEEKLY",
+        Frequency::Monthly => "MONTHLY",
+    };
+    let mut parts = vec![format!("FREQ={}", freq), format!("INTERVAL={}", rule.interval)];
+    if let Some(mask) = rule.weekdays {
+        let codes = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+        let days: Vec<&str> = (0..7).filter(|i| mask & (1 << i) != 0).map(|i| codes[i]).collect();
+        if !days.is_empty() {
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+    }
+    if let Some(until) = rule.until {
+        parts.push(format!("UNTIL={}", until.format("%Y-%m-%d")));
+    }
+    if let Some(count) = rule.count {
+        parts.push(format!("COUNT={}", count));
+    }
+    parts.join(";")
+}
+
+fn rule_from_string(s: &str) -> Option<RecurrenceRule> {
+    let mut frequency = None;
+    let mut interval = 1u32;
+    let mut weekdays = None;
+    let mut until = None;
+    let mut count = None;
+    for part in s.split(';') {
+        let (key, val) = part.split_once('=')?;
+        match key {
+            "FREQ" => frequency = match val {
+                "DAILY" => Some(Frequency::Daily),
+                "WEEKLY" => Some(Frequency::Weekly),
+                "MONTHLY" => Some(Frequency::Monthly),
+                _ => None,
+            },
+            "INTERVAL" => interval = val.parse().unwrap_or(1),
+            "BYDAY" => {
+                let codes = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+                let mut mask = 0u8;
+                for day in val.split(',') {
+                    if let Some(pos) = codes.iter().position(|c| *c == day) {
+                        mask |= 1 << pos;
+                    }
+                }
+                weekdays = Some(mask);
+            }
+            "UNTIL" => until = NaiveDate::parse_from_str(val, "%Y-%m-%d").ok(),
+            "COUNT" => count = val.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(RecurrenceRule { frequency: frequency?, interval, weekdays, until, count, exceptions: Vec::new() })
+}
+
+/// Resolves an `agenda` period keyword or explicit range into a half-open
+/// `[first, last)` date window.
+fn parse_agenda_range(input: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    match tokens.as_slice() {
+        ["day"] => Some((today, today + chrono::Duration::days(1))),
+        ["week"] => {
+            let days_from_monday = today.weekday().number_from_monday() as i64 - 1;
+            let first = today - chrono::Duration::days(days_from_monday);
+            Some((first, first + chrono::Duration::days(7)))
+        }
+        ["month"] => {
+            let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)?;
+            let next = add_months(first, 1)?;
+            Some((first, next))
+        }
+        [start, end] => {
+            let first = NaiveDate::parse_from_str(start, "%Y-%m-%d").ok()?;
+            let last = NaiveDate::parse_from_str(end, "%Y-%m-%d").ok()?;
+            Some((first, last))
+        }
+        _ => None,
+    }
+}
+
+/// Loops like `ask_u64` until the user enters a date we can parse, then
+/// normalizes it to canonical `YYYY-MM-DD`. Returns an empty string
+/// unparsed when `allow_blank` and the user enters nothing (used by
+/// `update_event`'s "leave blank to keep current" fields).
+fn ask_date(question: &str, allow_blank: bool) -> Result<String> {
+    loop {
+        let input = ask_details(question)?;
+        if allow_blank && input.is_empty() {
+            return Ok(String::new());
+        }
+        match parse_flexible_date(&input) {
+            Some(date) => return Ok(date.format("%Y-%m-%d").to_string()),
+            None => println!("Could not understand date '{}'. Try YYYY-MM-DD, MM/DD, today, tomorrow, or 'next monday'.", input),
+        }
+    }
+}
+
+/// Prompts for a multi-day event's last day via `ask_date`'s parse/reprompt
+/// loop; blank means the event stays single-day. Rejects an end date before
+/// `start` so an event can't span backward.
+fn ask_end_date(start: &str) -> Result<Option<String>> {
+    let start_date = NaiveDate::parse_from_str(start, "%Y-%m-%d").ok();
+    loop {
+        let end = ask_date("Enter the last day this event spans (blank for a single-day event): ", true)?;
+        if end.is_empty() {
+            return Ok(None);
+        }
+        let end_date = NaiveDate::parse_from_str(&end, "%Y-%m-%d").ok();
+        if end_date < start_date {
+            println!("End date can't be before the start date.");
+            continue;
+        }
+        return Ok(Some(end));
+    }
+}
+
+/// Loops like `ask_u64` until the user enters a time we can parse, then
+/// normalizes it to canonical `HH:MM`. See `ask_date` for `allow_blank`.
+fn ask_time(question: &str, allow_blank: bool) -> Result<String> {
+    loop {
+        let input = ask_details(question)?;
+        if allow_blank && input.is_empty() {
+            return Ok(String::new());
+        }
+        match parse_flexible_time(&input) {
+            Some(time) => return Ok(time.format("%H:%M").to_string()),
+            None => println!("Could not understand time '{}'. Try HH:MM, 3pm, or 3:30 PM.", input),
+        }
+    }
+}
+
+/// Parses ISO `YYYY-MM-DD`, `MM/DD` (rolled forward a year if it's already
+/// passed), `today`/`tomorrow`, and `next <weekday>`, resolving relative
+/// words against `Local::now()`.
+fn parse_flexible_date(input: &str) -> Option<NaiveDate> {
+    let trimmed = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let today = Local::now().date_naive();
+    let lower = trimmed.to_lowercase();
+    match lower.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return Some(today + chrono::Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(weekday_name) = lower.strip_prefix("next ")
+        && let Some(weekday) = parse_weekday_name(weekday_name)
+    {
+        return Some(next_weekday(today, weekday));
+    }
+
+    if let Some((m, d)) = trimmed.split_once('/')
+        && let (Ok(month), Ok(day)) = (m.parse::<u32>(), d.parse::<u32>())
+    {
+        let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+        return Some(if date < today {
+            NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+        } else {
+            date
+        });
+    }
+
+    None
+}
+
+fn parse_weekday_name(name: &str) -> Option<Weekday> {
+    match name {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The next date strictly after `from` that falls on `target` weekday.
+fn next_weekday(from: NaiveDate, target: Weekday) -> NaiveDate {
+    let from_num = from.weekday().num_days_from_monday() as i64;
+    let target_num = target.num_days_from_monday() as i64;
+    let mut delta = target_num - from_num;
+    if delta <= 0 {
+        delta += 7;
+    }
+    from + chrono::Duration::days(delta)
+}
+
+/// Parses `HH:MM` as well as `3pm`, `3:30pm`, and `3:30 PM` (case-insensitive).
+fn parse_flexible_time(input: &str) -> Option<NaiveTime> {
+    let trimmed = input.trim();
+    if let Ok(time) = NaiveTime::parse_from_str(trimmed, "%H:%M") {
+        return Some(time);
+    }
+    let upper = trimmed.to_uppercase();
+    // chrono won't parse a time without minutes, so an hour-only form like
+    // "3PM" or "3 PM" gets ":00" inserted after the hour first.
+    let hour_end = upper.find(|c: char| !c.is_ascii_digit()).unwrap_or(upper.len());
+    let with_minutes = if upper[hour_end..].starts_with(':') {
+        upper.clone()
+    } else {
+        format!("{}:00{}", &upper[..hour_end], &upper[hour_end..])
+    };
+    for fmt in ["%I:%M %p", "%I:%M%p"] {
+        if let Ok(time) = NaiveTime::parse_from_str(&with_minutes, fmt) {
+            return Some(time);
+        }
+    }
+    None
+}
+
+fn ask_yes_no(question: &str) -> Result<bool> {
+    loop {
+        let input = ask_details(question)?.to_lowercase();
+        match input.as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("Please enter y or n."),
+        }
+    }
+}
+
+/// Interactively builds a `RecurrenceRule` for `create_event`, looping on
+/// each field the way `ask_u64` already loops on invalid numbers.
+fn ask_recurrence() -> Result<Option<RecurrenceRule>> {
+    if !ask_yes_no("Repeat this event? (y/n): ")? {
+        return Ok(None);
+    }
+    let frequency = loop {
+        let input = ask_details("Frequency (daily/weekly/monthly): ")?.to_lowercase();
+        match input.as_str() {
+            "daily" => break Frequency::Daily,
+            "weekly" => break Frequency::Weekly,
+            "monthly" => break Frequency::Monthly,
+            _ => println!("Please enter daily, weekly, or monthly."),
+        }
+    };
+    let interval = (ask_u64("Repeat every N (interval): ")?.max(1)) as u32;
+    let weekdays = if frequency == Frequency::Weekly {
+        loop {
+            let input = ask_details(
+                "Which weekdays? (comma-separated MO,TU,WE,TH,FR,SA,SU, blank for the start date's weekday): "
+            )?;
+            if input.is_empty() {
+                break None;
+            }
+            let codes = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+            let mut mask = 0u8;
+            for day in input.to_uppercase().split(',') {
+                if let Some(pos) = codes.iter().position(|c| *c == day.trim()) {
+                    mask |= 1 << pos;
+                }
+            }
+            if mask != 0 {
+                break Some(mask);
+            }
+            println!("Please enter weekday codes such as MO,WE,FR.");
+        }
+    } else {
+        None
+    };
+    let (until, count) = if ask_yes_no("Repeat until a specific date instead of a count? (y/n): ")? {
+        let until = ask_date(
+            "Repeat until (YYYY-MM-DD, MM/DD, today, tomorrow, next <weekday>): ",
+            false,
+        )?;
+        (NaiveDate::parse_from_str(&until, "%Y-%m-%d").ok(), None)
+    } else {
+        let entered = ask_u64("Repeat how many times? (0 for no limit): ")? as u32;
+        (None, if entered == 0 { None } else { Some(entered) })
+    };
+    Ok(Some(RecurrenceRule { frequency, interval, weekdays, until, count, exceptions: Vec::new() }))
 }
 
 fn ask_details(question: &str) -> Result<String> {
@@ -223,48 +1408,96 @@ fn help() {
         update   - Update an event\n
         view     - View an event\n
         search   - Search for an event\n
+        import_ics - Import events from an .ics file\n
+        export_ics - Export the calendar to an .ics file\n
+        agenda [day|week|month|YYYY-MM-DD YYYY-MM-DD] - Show events in a date range, grouped by day\n
+        export_md   - Render a month/week grid to a Markdown file\n
+        export_html - Render a month/week grid to an HTML file\n
+        undo     - Undo the last create/delete/update\n
+        redo     - Redo the last undone change\n
+        use <name> - Switch the active calendar\n
+        calendars  - List loaded calendars\n
         help     - Show this help message\n
         exit     - Exit the program\n"
     );
 }
 
 #[inline(always)]
-fn handle_command(command: &str, calendar: &mut Calendar) -> Result<bool> {
-    match command {
+fn handle_command(command: &str, app: &mut App) -> Result<bool> {
+    let mut tokens = command.splitn(2, ' ');
+    let name = tokens.next().unwrap_or("");
+    let args = tokens.next().unwrap_or("").trim();
+    match name {
         "create" => {
-            calendar.create_event()?;
+            app.active_mut().create_event()?;
             Ok(true)
         }
         "delete" => {
-            calendar.delete_event()?;
+            app.active_mut().delete_event()?;
             Ok(true)
         }
         "list" => {
-            calendar.list_events()?;
+            app.list()?;
             Ok(true)
         }
         "load" => {
-            calendar.load()?;
+            app.load()?;
             Ok(true)
         }
         "save" => {
-            calendar.save()?;
+            app.save()?;
             Ok(true)
         }
         "upcoming" => {
-            calendar.upcoming_events()?;
+            app.upcoming()?;
             Ok(true)
         }
         "update" => {
-            calendar.update_event()?;
+            app.active_mut().update_event()?;
             Ok(true)
         }
         "view" => {
-            calendar.view()?;
+            app.active().view()?;
             Ok(true)
         }
         "search" => {
-            calendar.search()?;
+            app.search()?;
+            Ok(true)
+        }
+        "import_ics" => {
+            app.active_mut().import_ics()?;
+            Ok(true)
+        }
+        "export_ics" => {
+            app.active().export_ics()?;
+            Ok(true)
+        }
+        "agenda" => {
+            app.agenda(args)?;
+            Ok(true)
+        }
+        "export_md" => {
+            app.active().export_md()?;
+            Ok(true)
+        }
+        "export_html" => {
+            app.active().export_html()?;
+            Ok(true)
+        }
+        "undo" => {
+            app.active_mut().undo()?;
+            Ok(true)
+        }
+        "redo" => {
+            app.active_mut().redo()?;
+            Ok(true)
+        }
+        "use" => {
+            app.use_calendar(args)?;
+            Ok(true)
+        }
+        "calendars" => {
+            app.list_calendars()?;
             Ok(true)
         }
         "help" => {
@@ -273,7 +1506,7 @@ fn handle_command(command: &str, calendar: &mut Calendar) -> Result<bool> {
         }
         "exit" => Ok(false),
         _ => {
-            println!("Unknown command: {}", command);
+            println!("Unknown command: {}", name);
             Ok(true)
         }
     }
@@ -281,10 +1514,10 @@ fn handle_command(command: &str, calendar: &mut Calendar) -> Result<bool> {
 
 fn main() -> Result<()> {
     println!("Welcome to calendar!");
-    let mut calendar = Calendar::new();
-    loop {   
+    let mut app = App::new();
+    loop {
         let command = get_command()?;
-        match handle_command(&command, &mut calendar) {
+        match handle_command(&command, &mut app) {
             Ok(true) => continue,
             Ok(false) => break,
             Err(e) => {
@@ -295,3 +1528,342 @@ fn main() -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(frequency: Frequency) -> RecurrenceRule {
+        RecurrenceRule { frequency, interval: 1, weekdays: None, until: None, count: None, exceptions: Vec::new() }
+    }
+
+    fn event(id: u64, date: &str, end_date: Option<&str>) -> Event {
+        Event {
+            id,
+            title: format!("Event {id}"),
+            date: date.to_string(),
+            time: "09:00".to_string(),
+            description: String::new(),
+            recurrence: None,
+            end_date: end_date.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn pad_to_week_grid_aligns_to_monday() {
+        // 2026-07-01 is a Wednesday; the grid should pad back to the
+        // preceding Monday and forward to the following Monday.
+        let first = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2026, 8, 1).unwrap();
+        let (grid_start, grid_end) = pad_to_week_grid(first, last);
+        assert_eq!(grid_start, NaiveDate::from_ymd_opt(2026, 6, 29).unwrap());
+        assert_eq!(grid_start.weekday(), Weekday::Mon);
+        assert_eq!(grid_end, NaiveDate::from_ymd_opt(2026, 8, 3).unwrap());
+        assert_eq!(grid_end.weekday(), Weekday::Mon);
+    }
+
+    #[test]
+    fn pad_to_week_grid_is_noop_on_already_aligned_week() {
+        let first = NaiveDate::from_ymd_opt(2026, 6, 29).unwrap(); // Monday
+        let last = first + chrono::Duration::days(7);
+        let (grid_start, grid_end) = pad_to_week_grid(first, last);
+        assert_eq!(grid_start, first);
+        assert_eq!(grid_end, last);
+    }
+
+    #[test]
+    fn day_cells_carries_multi_day_event_forward() {
+        let mut cal = Calendar::new();
+        cal.events.push(event(1, "2026-07-01", Some("2026-07-03")));
+        let grid_start = NaiveDate::from_ymd_opt(2026, 6, 29).unwrap();
+        let grid_end = NaiveDate::from_ymd_opt(2026, 7, 6).unwrap();
+        let cells = cal.day_cells(grid_start, grid_end);
+        let on = |date: NaiveDate| cells.iter().find(|(d, _)| *d == date).unwrap().1.clone();
+        assert!(on(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap())[0].contains("Event 1"));
+        assert!(on(NaiveDate::from_ymd_opt(2026, 7, 2).unwrap())[0].contains("Event 1"));
+        assert!(on(NaiveDate::from_ymd_opt(2026, 7, 3).unwrap())[0].contains("Event 1"));
+        assert!(on(NaiveDate::from_ymd_opt(2026, 6, 30).unwrap()).is_empty());
+        assert!(on(NaiveDate::from_ymd_opt(2026, 7, 4).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn day_cells_clips_multi_day_event_to_grid_bounds() {
+        let mut cal = Calendar::new();
+        cal.events.push(event(1, "2026-06-28", Some("2026-07-05")));
+        let grid_start = NaiveDate::from_ymd_opt(2026, 6, 29).unwrap();
+        let grid_end = NaiveDate::from_ymd_opt(2026, 7, 6).unwrap();
+        let cells = cal.day_cells(grid_start, grid_end);
+        assert_eq!(cells.first().unwrap().0, grid_start);
+        assert!(cells.first().unwrap().1[0].contains("Event 1"));
+        assert_eq!(cells.last().unwrap().0, grid_end - chrono::Duration::days(1));
+        assert!(cells.last().unwrap().1[0].contains("Event 1"));
+    }
+
+    #[test]
+    fn daily_recurrence_respects_count() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut r = rule(Frequency::Daily);
+        r.count = Some(3);
+        let horizon = base + chrono::Duration::days(30);
+        let occurrences = expand_recurrence(base, &r, base, horizon);
+        assert_eq!(occurrences, vec![
+            base,
+            base + chrono::Duration::days(1),
+            base + chrono::Duration::days(2),
+        ]);
+    }
+
+    #[test]
+    fn weekly_recurrence_expands_byday_mask() {
+        // 2026-01-05 is a Monday.
+        let base = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        let mut r = rule(Frequency::Weekly);
+        r.weekdays = Some(0b0010101); // Mon, Wed, Fri
+        let first = base;
+        let last = base + chrono::Duration::days(6);
+        let occurrences = expand_recurrence(base, &r, first, last);
+        assert_eq!(occurrences, vec![
+            NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 7).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 9).unwrap(),
+        ]);
+    }
+
+    #[test]
+    fn until_excludes_dates_after_cutoff() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut r = rule(Frequency::Daily);
+        r.until = Some(base + chrono::Duration::days(2));
+        let horizon = base + chrono::Duration::days(30);
+        let occurrences = expand_recurrence(base, &r, base, horizon);
+        assert_eq!(occurrences, vec![
+            base,
+            base + chrono::Duration::days(1),
+            base + chrono::Duration::days(2),
+        ]);
+    }
+
+    #[test]
+    fn exception_dates_are_skipped() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut r = rule(Frequency::Daily);
+        r.count = Some(3);
+        r.exceptions = vec![base + chrono::Duration::days(1)];
+        let horizon = base + chrono::Duration::days(30);
+        let occurrences = expand_recurrence(base, &r, base, horizon);
+        assert_eq!(occurrences, vec![base, base + chrono::Duration::days(2)]);
+    }
+
+    #[test]
+    fn rule_to_string_and_back_round_trips() {
+        let mut r = rule(Frequency::Weekly);
+        r.interval = 2;
+        r.weekdays = Some(0b1000001); // Mon, Sun
+        r.until = Some(NaiveDate::from_ymd_opt(2026, 6, 1).unwrap());
+        let encoded = rule_to_string(&r);
+        let decoded = rule_from_string(&encoded).unwrap();
+        assert_eq!(decoded.frequency, r.frequency);
+        assert_eq!(decoded.interval, r.interval);
+        assert_eq!(decoded.weekdays, r.weekdays);
+        assert_eq!(decoded.until, r.until);
+    }
+
+    #[test]
+    fn parse_flexible_date_parses_iso_dates() {
+        assert_eq!(parse_flexible_date("2026-03-05"), NaiveDate::from_ymd_opt(2026, 3, 5));
+    }
+
+    #[test]
+    fn parse_flexible_date_resolves_today_and_tomorrow() {
+        let today = Local::now().date_naive();
+        assert_eq!(parse_flexible_date("today"), Some(today));
+        assert_eq!(parse_flexible_date("TOMORROW"), Some(today + chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn parse_flexible_date_next_weekday_always_lands_in_the_future() {
+        let today = Local::now().date_naive();
+        let resolved = parse_flexible_date("next friday").unwrap();
+        assert!(resolved > today);
+        assert_eq!(resolved.weekday(), Weekday::Fri);
+    }
+
+    #[test]
+    fn parse_flexible_date_mm_dd_stays_in_current_year_when_still_upcoming() {
+        let today = Local::now().date_naive();
+        let tomorrow = today + chrono::Duration::days(1);
+        let input = tomorrow.format("%m/%d").to_string();
+        let resolved = parse_flexible_date(&input).unwrap();
+        assert_eq!((resolved.year(), resolved.month(), resolved.day()), (tomorrow.year(), tomorrow.month(), tomorrow.day()));
+    }
+
+    #[test]
+    fn parse_flexible_date_mm_dd_rolls_over_to_next_year_once_passed() {
+        let today = Local::now().date_naive();
+        let yesterday = today - chrono::Duration::days(1);
+        let input = yesterday.format("%m/%d").to_string();
+        let resolved = parse_flexible_date(&input).unwrap();
+        assert_eq!(resolved.year(), yesterday.year() + 1);
+        assert_eq!((resolved.month(), resolved.day()), (yesterday.month(), yesterday.day()));
+    }
+
+    #[test]
+    fn next_weekday_rolls_to_the_following_week_when_today_is_the_target() {
+        let monday = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        assert_eq!(next_weekday(monday, Weekday::Mon), monday + chrono::Duration::days(7));
+    }
+
+    #[test]
+    fn next_weekday_lands_later_this_week_when_target_is_upcoming() {
+        let monday = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        assert_eq!(next_weekday(monday, Weekday::Fri), monday + chrono::Duration::days(4));
+    }
+
+    #[test]
+    fn parse_weekday_name_rejects_abbreviations() {
+        assert_eq!(parse_weekday_name("mon"), None);
+        assert_eq!(parse_weekday_name("monday"), Some(Weekday::Mon));
+    }
+
+    #[test]
+    fn merge_tagged_interleaves_sources_in_datetime_order() {
+        let dt = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let work_a = event(1, "2026-01-01", None);
+        let work_b = event(2, "2026-01-03", None);
+        let home_a = event(3, "2026-01-02", None);
+
+        let work = vec![(dt("2026-01-01 09:00"), &work_a), (dt("2026-01-03 09:00"), &work_b)];
+        let home = vec![(dt("2026-01-02 09:00"), &home_a)];
+
+        let merged = merge_tagged(vec![("work", work), ("home", home)]);
+        let order: Vec<(&str, u64)> = merged.iter().map(|(_, tag, e)| (*tag, e.id)).collect();
+        assert_eq!(order, vec![("work", 1), ("home", 3), ("work", 2)]);
+    }
+
+    #[test]
+    fn merge_tagged_breaks_ties_by_source_order() {
+        let dt = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let first = event(1, "2026-01-01", None);
+        let second = event(2, "2026-01-01", None);
+        let same_time = vec![(dt("2026-01-01 09:00"), &first)];
+        let also_same_time = vec![(dt("2026-01-01 09:00"), &second)];
+
+        let merged = merge_tagged(vec![("a", same_time), ("b", also_same_time)]);
+        let order: Vec<(&str, u64)> = merged.iter().map(|(_, tag, e)| (*tag, e.id)).collect();
+        assert_eq!(order, vec![("a", 1), ("b", 2)]);
+    }
+
+    #[test]
+    fn merge_tagged_handles_an_empty_source() {
+        let dt = NaiveDateTime::parse_from_str("2026-01-01 09:00", "%Y-%m-%d %H:%M").unwrap();
+        let ev = event(1, "2026-01-01", None);
+        let merged = merge_tagged(vec![("work", vec![(dt, &ev)]), ("home", Vec::new())]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].1, "work");
+    }
+
+    #[test]
+    fn undo_record_created_apply_removes_event_and_yields_deleted() {
+        let mut events = vec![event(1, "2026-01-01", None)];
+        let record = UndoRecord::Created { event: event(1, "2026-01-01", None) };
+        let inverse = record.apply(&mut events);
+        assert!(events.is_empty());
+        match inverse {
+            UndoRecord::Deleted { event, pos } => {
+                assert_eq!(event.id, 1);
+                assert_eq!(pos, 0);
+            }
+            _ => panic!("expected Deleted"),
+        }
+    }
+
+    #[test]
+    fn undo_record_deleted_apply_reinserts_event_at_its_original_position() {
+        let mut events = vec![event(1, "2026-01-01", None), event(3, "2026-01-03", None)];
+        let record = UndoRecord::Deleted { event: event(2, "2026-01-02", None), pos: 1 };
+        let inverse = record.apply(&mut events);
+        assert_eq!(events.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        match inverse {
+            UndoRecord::Created { event } => assert_eq!(event.id, 2),
+            _ => panic!("expected Created"),
+        }
+    }
+
+    #[test]
+    fn undo_record_updated_apply_swaps_in_the_prior_version() {
+        let mut before = event(1, "2026-01-01", None);
+        before.title = "Old title".to_string();
+        let mut events = vec![event(1, "2026-01-01", None)];
+        events[0].title = "New title".to_string();
+        let record = UndoRecord::Updated { before };
+        let inverse = record.apply(&mut events);
+        assert_eq!(events[0].title, "Old title");
+        match inverse {
+            UndoRecord::Updated { before } => assert_eq!(before.title, "New title"),
+            _ => panic!("expected Updated"),
+        }
+    }
+
+    #[test]
+    fn undo_record_round_trips_through_undo_and_redo() {
+        let mut pre_edit = event(1, "2026-01-01", None);
+        pre_edit.title = "Old title".to_string();
+        let mut events = vec![event(1, "2026-01-01", None)];
+        events[0].title = "New title".to_string();
+
+        let record = UndoRecord::Updated { before: pre_edit };
+        let redo_record = record.apply(&mut events); // undo: New -> Old
+        assert_eq!(events[0].title, "Old title");
+        let undo_record = redo_record.apply(&mut events); // redo: Old -> New
+        assert_eq!(events[0].title, "New title");
+        match undo_record {
+            UndoRecord::Updated { before } => assert_eq!(before.title, "Old title"),
+            _ => panic!("expected Updated"),
+        }
+    }
+
+    #[test]
+    fn escape_md_table_cell_prevents_pipe_from_adding_a_column() {
+        let line = "09:00 Budget | Planning";
+        let escaped = escape_md_table_cell(line);
+        assert_eq!(escaped, "09:00 Budget \\| Planning");
+        let row = format!("| {} |", escaped);
+        assert_eq!(row.matches('|').count(), 3); // leading, escaped, trailing
+    }
+
+    #[test]
+    fn escape_md_table_cell_leaves_plain_text_untouched() {
+        assert_eq!(escape_md_table_cell("09:00 Standup"), "09:00 Standup");
+    }
+
+    #[test]
+    fn html_escape_escapes_angle_brackets_and_ampersand() {
+        assert_eq!(html_escape("<script>Tom & Jerry</script>"), "&lt;script&gt;Tom &amp; Jerry&lt;/script&gt;");
+    }
+
+    #[test]
+    fn ics_escape_round_trips_special_characters() {
+        let text = "Meeting; agenda, notes\\action items\nfollow up";
+        let escaped = ics_escape_text(text);
+        assert_eq!(escaped, "Meeting\\; agenda\\, notes\\\\action items\\nfollow up");
+        assert_eq!(ics_unescape_text(&escaped), text);
+    }
+
+    #[test]
+    fn ics_fold_unfold_round_trips_long_line() {
+        let line = format!("SUMMARY:{}", "a".repeat(200));
+        let folded = ics_fold_line(&line);
+        assert!(folded.contains("\r\n "));
+        let unfolded = ics_unfold(&folded);
+        assert_eq!(unfolded, vec![line]);
+    }
+
+    #[test]
+    fn ics_fold_unfold_round_trips_short_line() {
+        let line = "UID:event-1@rust-cli-calendar".to_string();
+        let folded = ics_fold_line(&line);
+        assert_eq!(folded, format!("{}\r\n", line));
+        let unfolded = ics_unfold(&folded);
+        assert_eq!(unfolded, vec![line]);
+    }
+}